@@ -0,0 +1,87 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The execution context holds what has been registered for use by compiled expressions.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::error::{ExecutionError, Result};
+use super::physical_expr::scalar_function::ScalarFunction;
+
+/// Context for query execution. Holds the registered scalar user-defined functions.
+pub struct ExecutionContext {
+    scalar_functions: RefCell<HashMap<String, Rc<ScalarFunction>>>,
+}
+
+impl ExecutionContext {
+    pub fn new() -> Self {
+        ExecutionContext {
+            scalar_functions: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a scalar user-defined function so it can be called from expressions
+    /// as `Expr::ScalarFunction`.
+    pub fn register_udf(&self, f: ScalarFunction) {
+        self.scalar_functions
+            .borrow_mut()
+            .insert(f.name.clone(), Rc::new(f));
+    }
+
+    /// Looks up a previously registered scalar function by name.
+    pub fn get_udf(&self, name: &str) -> Result<Rc<ScalarFunction>> {
+        self.scalar_functions
+            .borrow()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ExecutionError::General(format!("Unknown function '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::DataType;
+
+    fn noop_udf(name: &str, arg_types: Vec<DataType>, return_type: DataType) -> ScalarFunction {
+        ScalarFunction::new(name, arg_types, return_type, Rc::new(|args| Ok(args[0].clone())))
+    }
+
+    #[test]
+    fn get_udf_finds_a_registered_function() {
+        let ctx = ExecutionContext::new();
+        ctx.register_udf(noop_udf("my_func", vec![DataType::Int32], DataType::Int32));
+        let fun = ctx.get_udf("my_func").unwrap();
+        assert_eq!(fun.name, "my_func");
+        assert_eq!(fun.arg_types, vec![DataType::Int32]);
+        assert_eq!(fun.return_type, DataType::Int32);
+    }
+
+    #[test]
+    fn get_udf_on_an_unknown_name_is_an_error() {
+        let ctx = ExecutionContext::new();
+        assert!(ctx.get_udf("does_not_exist").is_err());
+    }
+
+    #[test]
+    fn register_udf_replaces_a_previous_registration_with_the_same_name() {
+        let ctx = ExecutionContext::new();
+        ctx.register_udf(noop_udf("my_func", vec![DataType::Int32], DataType::Int32));
+        ctx.register_udf(noop_udf("my_func", vec![DataType::Float64], DataType::Float64));
+        let fun = ctx.get_udf("my_func").unwrap();
+        assert_eq!(fun.arg_types, vec![DataType::Float64]);
+    }
+}
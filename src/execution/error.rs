@@ -0,0 +1,27 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Error type shared by the execution engine.
+
+use std::result;
+
+#[derive(Debug, Clone)]
+pub enum ExecutionError {
+    /// Something is not yet supported by the execution engine
+    NotImplemented,
+    /// Catch-all for errors that don't warrant their own variant yet
+    General(String),
+}
+
+pub type Result<T> = result::Result<T, ExecutionError>;
@@ -15,46 +15,33 @@
 use std::rc::Rc;
 use std::sync::Arc;
 
-use arrow::array::*;
-use arrow::array_ops;
 use arrow::datatypes::{DataType, Schema};
-use arrow::record_batch::RecordBatch;
 
-use super::super::logicalplan::{Expr, Operator};
+use super::super::logicalplan::{Expr, ScalarValue};
 use super::context::ExecutionContext;
 use super::error::{ExecutionError, Result};
+use super::physical_expr::aggregate::{AggregateExpr, AggregateFunctionExpr};
+use super::physical_expr::{
+    coerce_types, BinaryExpr, CastExpr, Column, IsNullExpr, Literal, NotExpr, PhysicalExpr,
+    ScalarFunctionExpr,
+};
 
-/// Compiled Expression (basically just a closure to evaluate the expression at runtime)
-pub type CompiledExpr = Rc<Fn(&RecordBatch) -> Result<ArrayRef>>;
-
-pub type CompiledCastFunction = Rc<Fn(&ArrayRef) -> Result<ArrayRef>>;
-
-pub enum AggregateType {
-    Min,
-    Max,
-    Sum,
-    Count,
-    Avg,
-    //CountDistinct()
-}
-
-/// Runtime expression
+/// Runtime expression, produced by compiling a logical `Expr` against an input schema.
 pub enum RuntimeExpr {
     Compiled {
-        f: CompiledExpr,
+        expr: Arc<dyn PhysicalExpr>,
         t: DataType,
     },
     AggregateFunction {
-        f: AggregateType,
-        args: Vec<CompiledExpr>,
+        expr: Arc<dyn AggregateExpr>,
         t: DataType,
     },
 }
 
 impl RuntimeExpr {
-    pub fn get_func(&self) -> CompiledExpr {
+    pub fn expr(&self) -> Arc<dyn PhysicalExpr> {
         match self {
-            &RuntimeExpr::Compiled { ref f, .. } => f.clone(),
+            &RuntimeExpr::Compiled { ref expr, .. } => expr.clone(),
             _ => panic!(),
         }
     }
@@ -66,7 +53,7 @@ impl RuntimeExpr {
     }
 }
 
-/// Compiles a scalar expression into a closure
+/// Compiles a logical expression, including aggregate functions, into a `RuntimeExpr`.
 pub fn compile_expr(
     ctx: Rc<ExecutionContext>,
     expr: &Expr,
@@ -78,192 +65,82 @@ pub fn compile_expr(
             ref args,
             ref return_type,
         } => {
-            assert_eq!(1, args.len());
-
-            let compiled_args: Result<Vec<RuntimeExpr>> = args
-                .iter()
-                .map(|e| compile_scalar_expr(&ctx, e, input_schema))
-                .collect();
-
-            let func = match name.to_lowercase().as_ref() {
-                "min" => AggregateType::Min,
-                "max" => AggregateType::Max,
-                "count" => AggregateType::Count,
-                "sum" => AggregateType::Sum,
-                _ => unimplemented!("Unsupported aggregate function '{}'", name),
+            // ordered-set aggregates (PERCENTILE_CONT/DISC) take a fraction parameter
+            // in addition to the column being aggregated
+            assert!(args.len() == 1 || args.len() == 2);
+            let arg = compile_scalar_expr(&ctx, &args[0], input_schema)?;
+            let fraction = match args.get(1) {
+                Some(fraction_expr) => Some(extract_literal_f64(fraction_expr)?),
+                None => None,
             };
-
+            let expr: Arc<dyn AggregateExpr> = Arc::new(AggregateFunctionExpr::new(
+                name,
+                arg,
+                return_type.clone(),
+                fraction,
+                input_schema,
+            )?);
             Ok(RuntimeExpr::AggregateFunction {
-                f: func,
-                args: compiled_args?
-                    .iter()
-                    .map(|e| e.get_func().clone())
-                    .collect(),
+                expr,
                 t: return_type.clone(),
             })
         }
-        _ => Ok(compile_scalar_expr(&ctx, expr, input_schema)?),
+        _ => {
+            let expr = compile_scalar_expr(&ctx, expr, input_schema)?;
+            let t = expr.data_type(input_schema)?;
+            Ok(RuntimeExpr::Compiled { expr, t })
+        }
     }
 }
 
-macro_rules! binary_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
-        let ll = $LEFT.as_any().downcast_ref::<$DT>().unwrap();
-        let rr = $RIGHT.as_any().downcast_ref::<$DT>().unwrap();
-        Ok(Arc::new(array_ops::$OP(&ll, &rr)?))
-    }};
-}
-
-macro_rules! math_ops {
-    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $OP:ident) => {{
-        let left_values = $LEFT.get_func()($BATCH)?;
-        let right_values = $RIGHT.get_func()($BATCH)?;
-        match (left_values.data_type(), right_values.data_type()) {
-            (DataType::Int8, DataType::Int8) => {
-                binary_op!(left_values, right_values, $OP, Int8Array)
-            }
-            (DataType::Int16, DataType::Int16) => {
-                binary_op!(left_values, right_values, $OP, Int16Array)
-            }
-            (DataType::Int32, DataType::Int32) => {
-                binary_op!(left_values, right_values, $OP, Int32Array)
-            }
-            (DataType::Int64, DataType::Int64) => {
-                binary_op!(left_values, right_values, $OP, Int64Array)
-            }
-            (DataType::UInt8, DataType::UInt8) => {
-                binary_op!(left_values, right_values, $OP, UInt8Array)
-            }
-            (DataType::UInt16, DataType::UInt16) => {
-                binary_op!(left_values, right_values, $OP, UInt16Array)
-            }
-            (DataType::UInt32, DataType::UInt32) => {
-                binary_op!(left_values, right_values, $OP, UInt32Array)
-            }
-            (DataType::UInt64, DataType::UInt64) => {
-                binary_op!(left_values, right_values, $OP, UInt64Array)
-            }
-            (DataType::Float32, DataType::Float32) => {
-                binary_op!(left_values, right_values, $OP, Float32Array)
-            }
-            (DataType::Float64, DataType::Float64) => {
-                binary_op!(left_values, right_values, $OP, Float64Array)
-            }
-            _ => Err(ExecutionError::NotImplemented),
-        }
-    }};
+/// Extracts an `f64` out of a literal expression, e.g. the fraction argument of
+/// `PERCENTILE_CONT(0.5)`.
+fn extract_literal_f64(expr: &Expr) -> Result<f64> {
+    match expr {
+        &Expr::Literal(ScalarValue::Float64(v)) => Ok(v),
+        &Expr::Literal(ScalarValue::Float32(v)) => Ok(v as f64),
+        other => Err(ExecutionError::General(format!(
+            "expected a floating point literal, got {:?}",
+            other
+        ))),
+    }
 }
 
-macro_rules! comparison_ops {
-    ($LEFT:expr, $RIGHT:expr, $BATCH:expr, $OP:ident) => {{
-        let left_values = $LEFT.get_func()($BATCH)?;
-        let right_values = $RIGHT.get_func()($BATCH)?;
-        match (left_values.data_type(), right_values.data_type()) {
-            (DataType::Int8, DataType::Int8) => {
-                binary_op!(left_values, right_values, $OP, Int8Array)
-            }
-            (DataType::Int16, DataType::Int16) => {
-                binary_op!(left_values, right_values, $OP, Int16Array)
-            }
-            (DataType::Int32, DataType::Int32) => {
-                binary_op!(left_values, right_values, $OP, Int32Array)
-            }
-            (DataType::Int64, DataType::Int64) => {
-                binary_op!(left_values, right_values, $OP, Int64Array)
-            }
-            (DataType::UInt8, DataType::UInt8) => {
-                binary_op!(left_values, right_values, $OP, UInt8Array)
-            }
-            (DataType::UInt16, DataType::UInt16) => {
-                binary_op!(left_values, right_values, $OP, UInt16Array)
-            }
-            (DataType::UInt32, DataType::UInt32) => {
-                binary_op!(left_values, right_values, $OP, UInt32Array)
-            }
-            (DataType::UInt64, DataType::UInt64) => {
-                binary_op!(left_values, right_values, $OP, UInt64Array)
-            }
-            (DataType::Float32, DataType::Float32) => {
-                binary_op!(left_values, right_values, $OP, Float32Array)
-            }
-            (DataType::Float64, DataType::Float64) => {
-                binary_op!(left_values, right_values, $OP, Float64Array)
-            }
-            //TODO other types
-            _ => Err(ExecutionError::NotImplemented),
-        }
-    }};
+/// Wraps `expr` in a `CastExpr` to `target_type`, unless it is already that type.
+fn cast_to(
+    expr: Arc<dyn PhysicalExpr>,
+    current_type: &DataType,
+    target_type: &DataType,
+) -> Arc<dyn PhysicalExpr> {
+    if current_type == target_type {
+        expr
+    } else {
+        Arc::new(CastExpr::new(expr, target_type.clone()))
+    }
 }
 
-/// Compiles a scalar expression into a closure
+/// Compiles a scalar expression into a `PhysicalExpr`.
 pub fn compile_scalar_expr(
     ctx: &ExecutionContext,
     expr: &Expr,
     input_schema: &Schema,
-) -> Result<RuntimeExpr> {
+) -> Result<Arc<dyn PhysicalExpr>> {
     match expr {
-        &Expr::Literal(ref _lit) => {
-            Err(ExecutionError::NotImplemented)
-            //            let literal_value = lit.clone();
-            //            Ok(RuntimeExpr::Compiled {
-            //                f: Rc::new(move |_| {
-            //                    // literal values are a bit special - we don't repeat them in a vector
-            //                    // because it would be redundant, so we have a single value in a vector instead
-            //                    Ok(Value::Scalar(Rc::new(literal_value.clone())))
-            //                }),
-            //                t: DataType::Float64, //TODO
-            //            })
-        }
-        &Expr::Column(index) => Ok(RuntimeExpr::Compiled {
-            f: Rc::new(move |batch: &RecordBatch| Ok((*batch.column(index)).clone())),
-            t: input_schema.field(index).data_type().clone(),
-        }),
-        &Expr::Cast { ref expr, .. } => match expr.as_ref() {
-            &Expr::Column(_index) => {
-                Err(ExecutionError::NotImplemented)
-                //                let compiled_cast_expr = compile_cast_column(data_type.clone())?;
-                //                Ok(RuntimeExpr::Compiled {
-                //                    f: Rc::new(move |batch: &RecordBatch| {
-                //                        (compiled_cast_expr)(batch.column(index))
-                //                    }),
-                //                    t: data_type.clone(),
-                //                })
-            }
-            &Expr::Literal(ref _lit) => {
-                Err(ExecutionError::NotImplemented)
-                //                let compiled_cast_expr = compile_cast_scalar(lit, data_type)?;
-                //                Ok(RuntimeExpr::Compiled {
-                //                    f: Rc::new(move |_: &RecordBatch| {
-                //                        (compiled_cast_expr)(&Value::Scalar(Rc::new(ScalarValue::Null))) // pointless arg
-                //                    }),
-                //                    t: data_type.clone(),
-                //                })
+        &Expr::Literal(ref lit) => Ok(Arc::new(Literal::new(lit.clone()))),
+        &Expr::Column(index) => Ok(Arc::new(Column::new(index))),
+        &Expr::Cast {
+            ref expr,
+            ref data_type,
+        } => match expr.as_ref() {
+            &Expr::Column(_) | &Expr::Literal(_) => {
+                let compiled_expr = compile_scalar_expr(ctx, expr, input_schema)?;
+                Ok(Arc::new(CastExpr::new(compiled_expr, data_type.clone())))
             }
             other => Err(ExecutionError::General(format!(
                 "CAST not implemented for expression {:?}",
                 other
             ))),
         },
-        //        &Expr::IsNotNull(ref expr) => {
-        //            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema)?;
-        //            Ok(RuntimeExpr::Compiled {
-        //                f: Rc::new(move |batch: &RecordBatch| {
-        //                    let left_values = compiled_expr.get_func()(batch)?;
-        //                    left_values.is_not_null()
-        //                }),
-        //                t: DataType::Boolean,
-        //            })
-        //        }
-        //        &Expr::IsNull(ref expr) => {
-        //            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema)?;
-        //            Ok(RuntimeExpr::Compiled {
-        //                f: Rc::new(move |batch: &RecordBatch| {
-        //                    let left_values = compiled_expr.get_func()(batch)?;
-        //                    left_values.is_null()
-        //                }),
-        //                t: DataType::Boolean,
-        //            })
-        //        }
         &Expr::BinaryExpr {
             ref left,
             ref op,
@@ -271,87 +148,86 @@ pub fn compile_scalar_expr(
         } => {
             let left_expr = compile_scalar_expr(ctx, left, input_schema)?;
             let right_expr = compile_scalar_expr(ctx, right, input_schema)?;
-            let op_type = left_expr.get_type().clone();
-            match op {
-                &Operator::Eq => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, eq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::NotEq => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, neq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::Lt => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, lt)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::LtEq => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, lt_eq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::Gt => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, gt)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                &Operator::GtEq => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        comparison_ops!(left_expr, right_expr, batch, gt_eq)
-                    }),
-                    t: DataType::Boolean,
-                }),
-                //                    &Operator::And => Ok(RuntimeExpr::Compiled {
-                //                        f: Rc::new(move |batch: &RecordBatch| {
-                //                            let left_values = left_expr.get_func()(batch)?;
-                //                            let right_values = right_expr.get_func()(batch)?;
-                //                            left_values.and(&right_values)
-                //                        }),
-                //                        t: DataType::Boolean,
-                //                    }),
-                //                    &Operator::Or => Ok(RuntimeExpr::Compiled {
-                //                        f: Rc::new(move |batch: &RecordBatch| {
-                //                            let left_values = left_expr.get_func()(batch)?;
-                //                            let right_values = right_expr.get_func()(batch)?;
-                //                            left_values.or(&right_values)
-                //                        }),
-                //                        t: DataType::Boolean,
-                //                    }),
-                &Operator::Plus => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, add)
-                    }),
-                    t: op_type,
-                }),
-                &Operator::Minus => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, subtract)
-                    }),
-                    t: op_type,
-                }),
-                &Operator::Multiply => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, multiply)
-                    }),
-                    t: op_type,
-                }),
-                &Operator::Divide => Ok(RuntimeExpr::Compiled {
-                    f: Rc::new(move |batch: &RecordBatch| {
-                        math_ops!(left_expr, right_expr, batch, divide)
-                    }),
-                    t: op_type,
-                }),
-                _ => Err(ExecutionError::NotImplemented),
-            }
+            let left_type = left_expr.data_type(input_schema)?;
+            let right_type = right_expr.data_type(input_schema)?;
+            let (left_expr, right_expr) = match coerce_types(&left_type, &right_type, op) {
+                Some(ref common_type) => (
+                    cast_to(left_expr, &left_type, common_type),
+                    cast_to(right_expr, &right_type, common_type),
+                ),
+                None => (left_expr, right_expr),
+            };
+            Ok(Arc::new(BinaryExpr::new(left_expr, op.clone(), right_expr)))
+        }
+        &Expr::Not(ref expr) => {
+            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema)?;
+            Ok(Arc::new(NotExpr::new(compiled_expr)))
+        }
+        &Expr::IsNull(ref expr) => {
+            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema)?;
+            Ok(Arc::new(IsNullExpr::is_null(compiled_expr)))
+        }
+        &Expr::IsNotNull(ref expr) => {
+            let compiled_expr = compile_scalar_expr(ctx, expr, input_schema)?;
+            Ok(Arc::new(IsNullExpr::is_not_null(compiled_expr)))
+        }
+        &Expr::ScalarFunction { ref name, ref args, .. } => {
+            let fun = ctx.get_udf(name)?;
+            let compiled_args: Result<Vec<Arc<dyn PhysicalExpr>>> = args
+                .iter()
+                .map(|arg| compile_scalar_expr(ctx, arg, input_schema))
+                .collect();
+            let compiled_args = compiled_args?;
+            let actual_types: Result<Vec<DataType>> = compiled_args
+                .iter()
+                .map(|arg| arg.data_type(input_schema))
+                .collect();
+            validate_udf_call(name, &fun.arg_types, &actual_types?)?;
+            Ok(Arc::new(ScalarFunctionExpr::new(fun, compiled_args)))
         }
         _ => Err(ExecutionError::NotImplemented),
     }
 }
+
+/// Checks that a UDF call's argument count and types match its declaration, so a mismatch is
+/// reported at compile time rather than surfacing later as a downcast panic in the UDF body.
+fn validate_udf_call(name: &str, expected: &[DataType], actual: &[DataType]) -> Result<()> {
+    if actual.len() != expected.len() {
+        return Err(ExecutionError::General(format!(
+            "'{}' expects {} argument(s), got {}",
+            name,
+            expected.len(),
+            actual.len()
+        )));
+    }
+    for (i, (actual_type, expected_type)) in actual.iter().zip(expected).enumerate() {
+        if actual_type != expected_type {
+            return Err(ExecutionError::General(format!(
+                "'{}' expects argument {} to be {:?}, got {:?}",
+                name, i, expected_type, actual_type
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_udf_call_accepts_matching_arity_and_types() {
+        assert!(validate_udf_call("f", &[DataType::Int32, DataType::Utf8], &[DataType::Int32, DataType::Utf8]).is_ok());
+    }
+
+    #[test]
+    fn validate_udf_call_rejects_the_wrong_number_of_arguments() {
+        assert!(validate_udf_call("f", &[DataType::Int32], &[]).is_err());
+        assert!(validate_udf_call("f", &[DataType::Int32], &[DataType::Int32, DataType::Int32]).is_err());
+    }
+
+    #[test]
+    fn validate_udf_call_rejects_a_mismatched_argument_type() {
+        assert!(validate_udf_call("f", &[DataType::Int32], &[DataType::Utf8]).is_err());
+    }
+}
@@ -0,0 +1,810 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Schema};
+
+use super::super::super::logicalplan::ScalarValue;
+use super::super::error::{ExecutionError, Result};
+use super::PhysicalExpr;
+
+/// The aggregate functions understood by the execution engine.
+pub enum AggregateType {
+    Min,
+    Max,
+    Sum,
+    Count,
+    Avg,
+    CountDistinct,
+    BitAnd,
+    BitOr,
+    BitXor,
+    BoolAnd,
+    BoolOr,
+    /// `PERCENTILE_DISC`/`PERCENTILE_CONT`, parameterized by a fraction in `[0, 1]`
+    Percentile { continuous: bool },
+    Mode,
+}
+
+impl AggregateType {
+    fn from_name(name: &str) -> Result<AggregateType> {
+        match name.to_lowercase().as_ref() {
+            "min" => Ok(AggregateType::Min),
+            "max" => Ok(AggregateType::Max),
+            "sum" => Ok(AggregateType::Sum),
+            "count" => Ok(AggregateType::Count),
+            "avg" => Ok(AggregateType::Avg),
+            "count_distinct" => Ok(AggregateType::CountDistinct),
+            "bit_and" => Ok(AggregateType::BitAnd),
+            "bit_or" => Ok(AggregateType::BitOr),
+            "bit_xor" => Ok(AggregateType::BitXor),
+            "bool_and" => Ok(AggregateType::BoolAnd),
+            "bool_or" => Ok(AggregateType::BoolOr),
+            "percentile_disc" => Ok(AggregateType::Percentile { continuous: false }),
+            "percentile_cont" => Ok(AggregateType::Percentile { continuous: true }),
+            "mode" => Ok(AggregateType::Mode),
+            _ => Err(ExecutionError::General(format!(
+                "Unsupported aggregate function '{}'",
+                name
+            ))),
+        }
+    }
+
+    fn takes_fraction(&self) -> bool {
+        match self {
+            AggregateType::Percentile { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// An aggregate expression such as `SUM(a)`, pairing the aggregated argument with the
+/// accumulator that knows how to fold values into a running result.
+pub trait AggregateExpr: Debug {
+    /// The expression evaluated to produce the value fed into the accumulator.
+    fn expr(&self) -> Arc<dyn PhysicalExpr>;
+    /// The data type of the aggregated result.
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType>;
+    /// Create a new, empty accumulator to track this aggregate's running state.
+    fn create_accumulator(&self) -> Box<dyn Accumulator>;
+}
+
+/// Per-group running state for an aggregate expression.
+pub trait Accumulator {
+    /// Feed one more (possibly null) value into the running state.
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()>;
+    /// Produce the final aggregated value.
+    fn result(&self) -> Result<Option<ScalarValue>>;
+}
+
+/// `AggregateExpr` implementation shared by all of the built-in aggregate functions.
+#[derive(Debug)]
+pub struct AggregateFunctionExpr {
+    agg_type: AggregateType,
+    expr: Arc<dyn PhysicalExpr>,
+    return_type: DataType,
+    /// The fraction argument of an ordered-set aggregate, e.g. `PERCENTILE_CONT(0.9)`.
+    fraction: Option<f64>,
+}
+
+impl AggregateFunctionExpr {
+    /// Resolves `name` to an `AggregateType` and validates it against the aggregated
+    /// expression's data type and the `fraction` argument up front: `BIT_AND`/`BIT_OR`/
+    /// `BIT_XOR` require an integer column and a return type equal to that column's type,
+    /// `BOOL_AND`/`BOOL_OR` require a boolean column,
+    /// and ordered-set aggregates require a fraction in `[0, 1]` (all other aggregates
+    /// require none).
+    pub fn new(
+        name: &str,
+        expr: Arc<dyn PhysicalExpr>,
+        return_type: DataType,
+        fraction: Option<f64>,
+        input_schema: &Schema,
+    ) -> Result<Self> {
+        let agg_type = AggregateType::from_name(name)?;
+        match (agg_type.takes_fraction(), fraction) {
+            (true, Some(f)) if f < 0.0 || f > 1.0 => {
+                return Err(ExecutionError::General(format!(
+                    "fraction argument to '{}' must be between 0 and 1, got {}",
+                    name, f
+                )));
+            }
+            (true, Some(_)) => {}
+            (true, None) => {
+                return Err(ExecutionError::General(format!(
+                    "'{}' requires a fraction argument",
+                    name
+                )));
+            }
+            (false, None) => {}
+            (false, Some(_)) => {
+                return Err(ExecutionError::General(format!(
+                    "'{}' does not take a fraction argument",
+                    name
+                )));
+            }
+        }
+        match agg_type {
+            AggregateType::BitAnd | AggregateType::BitOr | AggregateType::BitXor => {
+                let arg_type = expr.data_type(input_schema)?;
+                if !is_integer(&arg_type) {
+                    return Err(ExecutionError::General(format!(
+                        "'{}' requires an integer column, got {:?}",
+                        name, arg_type
+                    )));
+                }
+                if return_type != arg_type {
+                    return Err(ExecutionError::General(format!(
+                        "'{}' returns its column's type {:?}, got return type {:?}",
+                        name, arg_type, return_type
+                    )));
+                }
+            }
+            AggregateType::BoolAnd | AggregateType::BoolOr => {
+                let arg_type = expr.data_type(input_schema)?;
+                if arg_type != DataType::Boolean {
+                    return Err(ExecutionError::General(format!(
+                        "'{}' requires a boolean column, got {:?}",
+                        name, arg_type
+                    )));
+                }
+            }
+            // PERCENTILE_CONT interpolates via `scalar_to_f64`, which only handles numeric
+            // scalars; PERCENTILE_DISC/MODE just return one of the input values verbatim, so
+            // they don't need this check.
+            AggregateType::Percentile { continuous: true } => {
+                let arg_type = expr.data_type(input_schema)?;
+                if !is_numeric(&arg_type) {
+                    return Err(ExecutionError::General(format!(
+                        "'{}' requires a numeric column, got {:?}",
+                        name, arg_type
+                    )));
+                }
+            }
+            _ => {}
+        }
+        Ok(AggregateFunctionExpr {
+            agg_type,
+            expr,
+            return_type,
+            fraction,
+        })
+    }
+}
+
+/// Whether `data_type` is a numeric type accepted by `PERCENTILE_CONT`.
+fn is_numeric(data_type: &DataType) -> bool {
+    is_integer(data_type) || data_type == &DataType::Float32 || data_type == &DataType::Float64
+}
+
+/// Whether `data_type` is one of the integer types accepted by the bitwise aggregates.
+fn is_integer(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => true,
+        _ => false,
+    }
+}
+
+impl Debug for AggregateType {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let name = match self {
+            AggregateType::Min => "Min",
+            AggregateType::Max => "Max",
+            AggregateType::Sum => "Sum",
+            AggregateType::Count => "Count",
+            AggregateType::Avg => "Avg",
+            AggregateType::CountDistinct => "CountDistinct",
+            AggregateType::BitAnd => "BitAnd",
+            AggregateType::BitOr => "BitOr",
+            AggregateType::BitXor => "BitXor",
+            AggregateType::BoolAnd => "BoolAnd",
+            AggregateType::BoolOr => "BoolOr",
+            AggregateType::Percentile { continuous: true } => "PercentileCont",
+            AggregateType::Percentile { continuous: false } => "PercentileDisc",
+            AggregateType::Mode => "Mode",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl AggregateExpr for AggregateFunctionExpr {
+    fn expr(&self) -> Arc<dyn PhysicalExpr> {
+        self.expr.clone()
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn create_accumulator(&self) -> Box<dyn Accumulator> {
+        match self.agg_type {
+            AggregateType::Min => Box::new(MinMaxAccumulator::new(true)),
+            AggregateType::Max => Box::new(MinMaxAccumulator::new(false)),
+            AggregateType::Sum => Box::new(SumAccumulator::new()),
+            AggregateType::Count => Box::new(CountAccumulator::new()),
+            AggregateType::Avg => Box::new(AvgAccumulator::new()),
+            AggregateType::CountDistinct => Box::new(CountDistinctAccumulator::new()),
+            AggregateType::BitAnd => Box::new(BitwiseAccumulator::new(BitwiseOp::And, &self.return_type)),
+            AggregateType::BitOr => Box::new(BitwiseAccumulator::new(BitwiseOp::Or, &self.return_type)),
+            AggregateType::BitXor => Box::new(BitwiseAccumulator::new(BitwiseOp::Xor, &self.return_type)),
+            AggregateType::BoolAnd => Box::new(BooleanAccumulator::new(true)),
+            AggregateType::BoolOr => Box::new(BooleanAccumulator::new(false)),
+            AggregateType::Percentile { continuous } => Box::new(PercentileAccumulator::new(
+                continuous,
+                self.fraction.expect("validated by AggregateFunctionExpr::new"),
+            )),
+            AggregateType::Mode => Box::new(ModeAccumulator::new()),
+        }
+    }
+}
+
+/// Tracks the smallest (or largest) non-null value seen so far.
+struct MinMaxAccumulator {
+    is_min: bool,
+    value: Option<ScalarValue>,
+}
+
+impl MinMaxAccumulator {
+    fn new(is_min: bool) -> Self {
+        MinMaxAccumulator {
+            is_min,
+            value: None,
+        }
+    }
+}
+
+impl Accumulator for MinMaxAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            self.value = Some(match self.value.take() {
+                None => v.clone(),
+                Some(ref current) => {
+                    let replace = if self.is_min { v < current } else { v > current };
+                    if replace {
+                        v.clone()
+                    } else {
+                        current.clone()
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(self.value.clone())
+    }
+}
+
+/// Running total of all non-null values seen so far.
+struct SumAccumulator {
+    value: Option<ScalarValue>,
+}
+
+impl SumAccumulator {
+    fn new() -> Self {
+        SumAccumulator { value: None }
+    }
+}
+
+impl Accumulator for SumAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            self.value = Some(match self.value.take() {
+                None => v.clone(),
+                Some(current) => add_scalars(&current, v),
+            });
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(self.value.clone())
+    }
+}
+
+/// Count of all non-null values seen so far.
+struct CountAccumulator {
+    count: i64,
+}
+
+impl CountAccumulator {
+    fn new() -> Self {
+        CountAccumulator { count: 0 }
+    }
+}
+
+impl Accumulator for CountAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if value.is_some() {
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(Some(ScalarValue::Int64(self.count)))
+    }
+}
+
+/// Running sum and count of all non-null values seen so far, divided at finalization.
+struct AvgAccumulator {
+    sum: Option<ScalarValue>,
+    count: i64,
+}
+
+impl AvgAccumulator {
+    fn new() -> Self {
+        AvgAccumulator {
+            sum: None,
+            count: 0,
+        }
+    }
+}
+
+impl Accumulator for AvgAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            self.sum = Some(match self.sum.take() {
+                None => v.clone(),
+                Some(current) => add_scalars(&current, v),
+            });
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(match self.sum {
+            None => None,
+            Some(ref sum) => Some(ScalarValue::Float64(scalar_to_f64(sum) / self.count as f64)),
+        })
+    }
+}
+
+/// Number of distinct non-null values seen so far.
+struct CountDistinctAccumulator {
+    seen: HashSet<ScalarValue>,
+}
+
+impl CountDistinctAccumulator {
+    fn new() -> Self {
+        CountDistinctAccumulator {
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl Accumulator for CountDistinctAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            self.seen.insert(v.clone());
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(Some(ScalarValue::Int64(self.seen.len() as i64)))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+/// Folds `AND`/`OR`/`XOR` across all non-null values of an integer column.
+struct BitwiseAccumulator {
+    op: BitwiseOp,
+    value: ScalarValue,
+}
+
+impl BitwiseAccumulator {
+    /// `data_type` must already have been validated as an integer type by
+    /// `AggregateFunctionExpr::new`.
+    fn new(op: BitwiseOp, data_type: &DataType) -> Self {
+        let identity = match op {
+            BitwiseOp::And => bitwise_all_ones(data_type),
+            BitwiseOp::Or | BitwiseOp::Xor => bitwise_zero(data_type),
+        }
+        .expect("validated by AggregateFunctionExpr::new");
+        BitwiseAccumulator {
+            op,
+            value: identity,
+        }
+    }
+}
+
+impl Accumulator for BitwiseAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            self.value = bitwise_fold(self.op, &self.value, v);
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(Some(self.value.clone()))
+    }
+}
+
+/// Logical `AND`/`OR` across all non-null values of a boolean column; `None` (`NULL`) until
+/// the first non-null value arrives.
+struct BooleanAccumulator {
+    is_and: bool,
+    value: Option<bool>,
+}
+
+impl BooleanAccumulator {
+    fn new(is_and: bool) -> Self {
+        BooleanAccumulator {
+            is_and,
+            value: None,
+        }
+    }
+}
+
+impl Accumulator for BooleanAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        match value {
+            None => {}
+            Some(ScalarValue::Boolean(v)) => {
+                self.value = Some(match self.value {
+                    None => *v,
+                    Some(current) => {
+                        if self.is_and {
+                            current && *v
+                        } else {
+                            current || *v
+                        }
+                    }
+                });
+            }
+            Some(other) => {
+                return Err(ExecutionError::General(format!(
+                    "{} requires a boolean column, got {:?}",
+                    if self.is_and { "BOOL_AND" } else { "BOOL_OR" },
+                    other
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        Ok(self.value.map(ScalarValue::Boolean))
+    }
+}
+
+/// Buffers every non-null value seen so far, sorting at finalization to compute
+/// `PERCENTILE_DISC`/`PERCENTILE_CONT`.
+struct PercentileAccumulator {
+    continuous: bool,
+    fraction: f64,
+    values: Vec<ScalarValue>,
+}
+
+impl PercentileAccumulator {
+    fn new(continuous: bool, fraction: f64) -> Self {
+        PercentileAccumulator {
+            continuous,
+            fraction,
+            values: Vec::new(),
+        }
+    }
+}
+
+impl Accumulator for PercentileAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            self.values.push(v.clone());
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        if self.values.is_empty() {
+            return Ok(None);
+        }
+        let mut sorted = self.values.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).expect("aggregated column is not orderable"));
+        let n = sorted.len();
+        if self.continuous {
+            let rank = self.fraction * (n - 1) as f64;
+            let lo = rank.floor() as usize;
+            let hi = rank.ceil() as usize;
+            let lo_value = scalar_to_f64(&sorted[lo]);
+            let hi_value = scalar_to_f64(&sorted[hi]);
+            let interpolated = lo_value + (hi_value - lo_value) * (rank - lo as f64);
+            Ok(Some(ScalarValue::Float64(interpolated)))
+        } else {
+            let index = ((self.fraction * n as f64).ceil() as usize)
+                .saturating_sub(1)
+                .min(n - 1);
+            Ok(Some(sorted[index].clone()))
+        }
+    }
+}
+
+/// Tracks a frequency count of every non-null value seen so far, to find the most frequent
+/// value (ties broken by the smallest value) at finalization.
+struct ModeAccumulator {
+    counts: HashMap<ScalarValue, i64>,
+}
+
+impl ModeAccumulator {
+    fn new() -> Self {
+        ModeAccumulator {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl Accumulator for ModeAccumulator {
+    fn accumulate(&mut self, value: &Option<ScalarValue>) -> Result<()> {
+        if let Some(ref v) = value {
+            *self.counts.entry(v.clone()).or_insert(0) += 1;
+        }
+        Ok(())
+    }
+
+    fn result(&self) -> Result<Option<ScalarValue>> {
+        let mode = self
+            .counts
+            .iter()
+            .max_by(|(a_value, a_count), (b_value, b_count)| {
+                a_count
+                    .cmp(b_count)
+                    .then_with(|| b_value.partial_cmp(a_value).unwrap())
+            })
+            .map(|(value, _)| value.clone());
+        Ok(mode)
+    }
+}
+
+/// Adds two scalars of the same numeric variant together.
+fn add_scalars(a: &ScalarValue, b: &ScalarValue) -> ScalarValue {
+    match (a, b) {
+        (ScalarValue::Int8(x), ScalarValue::Int8(y)) => ScalarValue::Int8(x + y),
+        (ScalarValue::Int16(x), ScalarValue::Int16(y)) => ScalarValue::Int16(x + y),
+        (ScalarValue::Int32(x), ScalarValue::Int32(y)) => ScalarValue::Int32(x + y),
+        (ScalarValue::Int64(x), ScalarValue::Int64(y)) => ScalarValue::Int64(x + y),
+        (ScalarValue::UInt8(x), ScalarValue::UInt8(y)) => ScalarValue::UInt8(x + y),
+        (ScalarValue::UInt16(x), ScalarValue::UInt16(y)) => ScalarValue::UInt16(x + y),
+        (ScalarValue::UInt32(x), ScalarValue::UInt32(y)) => ScalarValue::UInt32(x + y),
+        (ScalarValue::UInt64(x), ScalarValue::UInt64(y)) => ScalarValue::UInt64(x + y),
+        (ScalarValue::Float32(x), ScalarValue::Float32(y)) => ScalarValue::Float32(x + y),
+        (ScalarValue::Float64(x), ScalarValue::Float64(y)) => ScalarValue::Float64(x + y),
+        _ => panic!("cannot add mismatched scalar types {:?} and {:?}", a, b),
+    }
+}
+
+/// Widens any numeric scalar to an `f64`, for use at the end of an `AVG` computation.
+fn scalar_to_f64(v: &ScalarValue) -> f64 {
+    match v {
+        ScalarValue::Int8(x) => *x as f64,
+        ScalarValue::Int16(x) => *x as f64,
+        ScalarValue::Int32(x) => *x as f64,
+        ScalarValue::Int64(x) => *x as f64,
+        ScalarValue::UInt8(x) => *x as f64,
+        ScalarValue::UInt16(x) => *x as f64,
+        ScalarValue::UInt32(x) => *x as f64,
+        ScalarValue::UInt64(x) => *x as f64,
+        ScalarValue::Float32(x) => *x as f64,
+        ScalarValue::Float64(x) => *x,
+        other => panic!("cannot average non-numeric value {:?}", other),
+    }
+}
+
+/// The all-ones bit pattern (identity for `AND`) of an integer `DataType`.
+fn bitwise_all_ones(data_type: &DataType) -> Result<ScalarValue> {
+    match data_type {
+        DataType::Int8 => Ok(ScalarValue::Int8(-1)),
+        DataType::Int16 => Ok(ScalarValue::Int16(-1)),
+        DataType::Int32 => Ok(ScalarValue::Int32(-1)),
+        DataType::Int64 => Ok(ScalarValue::Int64(-1)),
+        DataType::UInt8 => Ok(ScalarValue::UInt8(!0)),
+        DataType::UInt16 => Ok(ScalarValue::UInt16(!0)),
+        DataType::UInt32 => Ok(ScalarValue::UInt32(!0)),
+        DataType::UInt64 => Ok(ScalarValue::UInt64(!0)),
+        other => Err(ExecutionError::General(format!(
+            "bitwise aggregates require an integer column, got {:?}",
+            other
+        ))),
+    }
+}
+
+/// Zero (identity for `OR`/`XOR`) of an integer `DataType`.
+fn bitwise_zero(data_type: &DataType) -> Result<ScalarValue> {
+    match data_type {
+        DataType::Int8 => Ok(ScalarValue::Int8(0)),
+        DataType::Int16 => Ok(ScalarValue::Int16(0)),
+        DataType::Int32 => Ok(ScalarValue::Int32(0)),
+        DataType::Int64 => Ok(ScalarValue::Int64(0)),
+        DataType::UInt8 => Ok(ScalarValue::UInt8(0)),
+        DataType::UInt16 => Ok(ScalarValue::UInt16(0)),
+        DataType::UInt32 => Ok(ScalarValue::UInt32(0)),
+        DataType::UInt64 => Ok(ScalarValue::UInt64(0)),
+        other => Err(ExecutionError::General(format!(
+            "bitwise aggregates require an integer column, got {:?}",
+            other
+        ))),
+    }
+}
+
+fn bitwise_fold(op: BitwiseOp, a: &ScalarValue, b: &ScalarValue) -> ScalarValue {
+    macro_rules! fold {
+        ($x:expr, $y:expr) => {
+            match op {
+                BitwiseOp::And => $x & $y,
+                BitwiseOp::Or => $x | $y,
+                BitwiseOp::Xor => $x ^ $y,
+            }
+        };
+    }
+    match (a, b) {
+        (ScalarValue::Int8(x), ScalarValue::Int8(y)) => ScalarValue::Int8(fold!(x, y)),
+        (ScalarValue::Int16(x), ScalarValue::Int16(y)) => ScalarValue::Int16(fold!(x, y)),
+        (ScalarValue::Int32(x), ScalarValue::Int32(y)) => ScalarValue::Int32(fold!(x, y)),
+        (ScalarValue::Int64(x), ScalarValue::Int64(y)) => ScalarValue::Int64(fold!(x, y)),
+        (ScalarValue::UInt8(x), ScalarValue::UInt8(y)) => ScalarValue::UInt8(fold!(x, y)),
+        (ScalarValue::UInt16(x), ScalarValue::UInt16(y)) => ScalarValue::UInt16(fold!(x, y)),
+        (ScalarValue::UInt32(x), ScalarValue::UInt32(y)) => ScalarValue::UInt32(fold!(x, y)),
+        (ScalarValue::UInt64(x), ScalarValue::UInt64(y)) => ScalarValue::UInt64(fold!(x, y)),
+        _ => panic!("cannot fold mismatched scalar types {:?} and {:?}", a, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_and_identity_is_all_ones() {
+        let mut acc = BitwiseAccumulator::new(BitwiseOp::And, &DataType::Int32);
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(-1)));
+        acc.accumulate(&Some(ScalarValue::Int32(0b1100))).unwrap();
+        acc.accumulate(&Some(ScalarValue::Int32(0b1010))).unwrap();
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(0b1000)));
+    }
+
+    #[test]
+    fn bit_or_identity_is_zero() {
+        let mut acc = BitwiseAccumulator::new(BitwiseOp::Or, &DataType::Int32);
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(0)));
+        acc.accumulate(&Some(ScalarValue::Int32(0b1100))).unwrap();
+        acc.accumulate(&Some(ScalarValue::Int32(0b0010))).unwrap();
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(0b1110)));
+    }
+
+    #[test]
+    fn bool_and_short_circuits_on_false_and_skips_nulls() {
+        let mut acc = BooleanAccumulator::new(true);
+        acc.accumulate(&Some(ScalarValue::Boolean(true))).unwrap();
+        acc.accumulate(&None).unwrap();
+        acc.accumulate(&Some(ScalarValue::Boolean(false))).unwrap();
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Boolean(false)));
+    }
+
+    #[test]
+    fn bool_and_rejects_a_non_boolean_value() {
+        let mut acc = BooleanAccumulator::new(true);
+        let err = acc.accumulate(&Some(ScalarValue::Int32(1)));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn bitwise_identity_rejects_a_non_integer_column() {
+        assert!(bitwise_all_ones(&DataType::Boolean).is_err());
+        assert!(bitwise_zero(&DataType::Utf8).is_err());
+    }
+
+    fn accumulate_all(acc: &mut dyn Accumulator, values: &[ScalarValue]) {
+        for v in values {
+            acc.accumulate(&Some(v.clone())).unwrap();
+        }
+    }
+
+    #[test]
+    fn percentile_disc_picks_the_value_at_the_rounded_up_rank() {
+        let mut acc = PercentileAccumulator::new(false, 0.5);
+        accumulate_all(
+            &mut acc,
+            &[
+                ScalarValue::Int32(10),
+                ScalarValue::Int32(20),
+                ScalarValue::Int32(30),
+                ScalarValue::Int32(40),
+            ],
+        );
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(20)));
+    }
+
+    #[test]
+    fn percentile_disc_clamps_to_the_last_value_at_fraction_one() {
+        let mut acc = PercentileAccumulator::new(false, 1.0);
+        accumulate_all(&mut acc, &[ScalarValue::Int32(10), ScalarValue::Int32(20)]);
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(20)));
+    }
+
+    #[test]
+    fn percentile_cont_interpolates_between_the_two_nearest_values() {
+        let mut acc = PercentileAccumulator::new(true, 0.5);
+        accumulate_all(
+            &mut acc,
+            &[
+                ScalarValue::Int32(10),
+                ScalarValue::Int32(20),
+                ScalarValue::Int32(30),
+                ScalarValue::Int32(40),
+            ],
+        );
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Float64(25.0)));
+    }
+
+    #[test]
+    fn percentile_cont_is_exact_when_the_rank_is_a_whole_number() {
+        let mut acc = PercentileAccumulator::new(true, 0.0);
+        accumulate_all(&mut acc, &[ScalarValue::Int32(10), ScalarValue::Int32(20)]);
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Float64(10.0)));
+    }
+
+    #[test]
+    fn percentile_of_empty_input_is_null() {
+        let acc = PercentileAccumulator::new(true, 0.5);
+        assert_eq!(acc.result().unwrap(), None);
+    }
+
+    #[test]
+    fn percentile_cont_requires_a_numeric_column() {
+        assert!(is_numeric(&DataType::Int32));
+        assert!(is_numeric(&DataType::Float64));
+        assert!(!is_numeric(&DataType::Utf8));
+        assert!(!is_numeric(&DataType::Boolean));
+    }
+
+    #[test]
+    fn mode_breaks_ties_with_the_smallest_value() {
+        let mut acc = ModeAccumulator::new();
+        accumulate_all(
+            &mut acc,
+            &[
+                ScalarValue::Int32(5),
+                ScalarValue::Int32(5),
+                ScalarValue::Int32(1),
+                ScalarValue::Int32(1),
+                ScalarValue::Int32(9),
+            ],
+        );
+        assert_eq!(acc.result().unwrap(), Some(ScalarValue::Int32(1)));
+    }
+
+    #[test]
+    fn mode_of_empty_input_is_null() {
+        let acc = ModeAccumulator::new();
+        assert_eq!(acc.result().unwrap(), None);
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::array_ops;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::super::logicalplan::Operator;
+use super::super::error::{ExecutionError, Result};
+use super::PhysicalExpr;
+
+macro_rules! binary_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
+        let ll = $LEFT.as_any().downcast_ref::<$DT>().unwrap();
+        let rr = $RIGHT.as_any().downcast_ref::<$DT>().unwrap();
+        Ok(Arc::new(array_ops::$OP(&ll, &rr)?) as ArrayRef)
+    }};
+}
+
+/// `AND` per SQL three-valued logic: `false` wins even if the other side is null, two
+/// `true`s give `true`, and anything else (a null combined with a non-false) is null.
+fn and(left: &ArrayRef, right: &ArrayRef) -> Result<ArrayRef> {
+    let ll = left.as_any().downcast_ref::<BooleanArray>().unwrap();
+    let rr = right.as_any().downcast_ref::<BooleanArray>().unwrap();
+    let mut builder = BooleanBuilder::new(ll.len());
+    for i in 0..ll.len() {
+        let l = if ll.is_null(i) { None } else { Some(ll.value(i)) };
+        let r = if rr.is_null(i) { None } else { Some(rr.value(i)) };
+        match (l, r) {
+            (Some(false), _) | (_, Some(false)) => builder.append_value(false)?,
+            (Some(true), Some(true)) => builder.append_value(true)?,
+            _ => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+/// `OR` per SQL three-valued logic: `true` wins even if the other side is null, two
+/// `false`s give `false`, and anything else (a null combined with a non-true) is null.
+fn or(left: &ArrayRef, right: &ArrayRef) -> Result<ArrayRef> {
+    let ll = left.as_any().downcast_ref::<BooleanArray>().unwrap();
+    let rr = right.as_any().downcast_ref::<BooleanArray>().unwrap();
+    let mut builder = BooleanBuilder::new(ll.len());
+    for i in 0..ll.len() {
+        let l = if ll.is_null(i) { None } else { Some(ll.value(i)) };
+        let r = if rr.is_null(i) { None } else { Some(rr.value(i)) };
+        match (l, r) {
+            (Some(true), _) | (_, Some(true)) => builder.append_value(true)?,
+            (Some(false), Some(false)) => builder.append_value(false)?,
+            _ => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+macro_rules! numeric_ops {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        match ($LEFT.data_type(), $RIGHT.data_type()) {
+            (DataType::Int8, DataType::Int8) => binary_op!($LEFT, $RIGHT, $OP, Int8Array),
+            (DataType::Int16, DataType::Int16) => binary_op!($LEFT, $RIGHT, $OP, Int16Array),
+            (DataType::Int32, DataType::Int32) => binary_op!($LEFT, $RIGHT, $OP, Int32Array),
+            (DataType::Int64, DataType::Int64) => binary_op!($LEFT, $RIGHT, $OP, Int64Array),
+            (DataType::UInt8, DataType::UInt8) => binary_op!($LEFT, $RIGHT, $OP, UInt8Array),
+            (DataType::UInt16, DataType::UInt16) => binary_op!($LEFT, $RIGHT, $OP, UInt16Array),
+            (DataType::UInt32, DataType::UInt32) => binary_op!($LEFT, $RIGHT, $OP, UInt32Array),
+            (DataType::UInt64, DataType::UInt64) => binary_op!($LEFT, $RIGHT, $OP, UInt64Array),
+            (DataType::Float32, DataType::Float32) => binary_op!($LEFT, $RIGHT, $OP, Float32Array),
+            (DataType::Float64, DataType::Float64) => binary_op!($LEFT, $RIGHT, $OP, Float64Array),
+            _ => Err(ExecutionError::NotImplemented),
+        }
+    }};
+}
+
+/// A binary expression such as `a + b` or `a > b`.
+#[derive(Debug)]
+pub struct BinaryExpr {
+    left: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    right: Arc<dyn PhysicalExpr>,
+}
+
+impl BinaryExpr {
+    pub fn new(left: Arc<dyn PhysicalExpr>, op: Operator, right: Arc<dyn PhysicalExpr>) -> Self {
+        BinaryExpr { left, op, right }
+    }
+
+    fn produces_boolean(&self) -> bool {
+        match self.op {
+            Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq
+            | Operator::And
+            | Operator::Or => true,
+            _ => false,
+        }
+    }
+}
+
+impl PhysicalExpr for BinaryExpr {
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        if self.produces_boolean() {
+            Ok(DataType::Boolean)
+        } else {
+            self.left.data_type(input_schema)
+        }
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        Ok(self.left.nullable(input_schema)? || self.right.nullable(input_schema)?)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let left_values = self.left.evaluate(batch)?;
+        let right_values = self.right.evaluate(batch)?;
+        match self.op {
+            Operator::Eq => numeric_ops!(left_values, right_values, eq),
+            Operator::NotEq => numeric_ops!(left_values, right_values, neq),
+            Operator::Lt => numeric_ops!(left_values, right_values, lt),
+            Operator::LtEq => numeric_ops!(left_values, right_values, lt_eq),
+            Operator::Gt => numeric_ops!(left_values, right_values, gt),
+            Operator::GtEq => numeric_ops!(left_values, right_values, gt_eq),
+            Operator::Plus => numeric_ops!(left_values, right_values, add),
+            Operator::Minus => numeric_ops!(left_values, right_values, subtract),
+            Operator::Multiply => numeric_ops!(left_values, right_values, multiply),
+            Operator::Divide => numeric_ops!(left_values, right_values, divide),
+            Operator::And => and(&left_values, &right_values),
+            Operator::Or => or(&left_values, &right_values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bool_array(values: Vec<Option<bool>>) -> ArrayRef {
+        let mut builder = BooleanBuilder::new(values.len());
+        for v in values {
+            match v {
+                Some(v) => builder.append_value(v).unwrap(),
+                None => builder.append_null().unwrap(),
+            }
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    }
+
+    fn bool_values(array: &ArrayRef) -> Vec<Option<bool>> {
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        (0..array.len())
+            .map(|i| if array.is_null(i) { None } else { Some(array.value(i)) })
+            .collect()
+    }
+
+    #[test]
+    fn and_propagates_null_per_three_valued_logic() {
+        let left = bool_array(vec![Some(true), Some(false), None, None, Some(true)]);
+        let right = bool_array(vec![None, None, Some(false), None, Some(true)]);
+        let result = and(&left, &right).unwrap();
+        assert_eq!(
+            bool_values(&result),
+            vec![None, Some(false), Some(false), None, Some(true)]
+        );
+    }
+
+    #[test]
+    fn or_propagates_null_per_three_valued_logic() {
+        let left = bool_array(vec![Some(true), Some(false), None, None, Some(false)]);
+        let right = bool_array(vec![None, None, Some(true), None, Some(false)]);
+        let result = or(&left, &right).unwrap();
+        assert_eq!(
+            bool_values(&result),
+            vec![Some(true), None, Some(true), None, Some(false)]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::error::{ExecutionError, Result};
+use super::PhysicalExpr;
+
+/// A function that casts one array into another of a different (compatible) data type.
+pub type CompiledCastFunction = Rc<Fn(&ArrayRef) -> Result<ArrayRef>>;
+
+/// Casts every (non-null) value of `$FROM_ARR` into `$TO_BUILDER`, preserving nulls.
+macro_rules! cast_numeric_array {
+    ($FROM_ARR:ident, $TO_BUILDER:ident, $TO_TY:ty) => {{
+        Rc::new(|array: &ArrayRef| {
+            let array = array.as_any().downcast_ref::<$FROM_ARR>().unwrap();
+            let mut builder = $TO_BUILDER::new(array.len());
+            for i in 0..array.len() {
+                if array.is_null(i) {
+                    builder.append_null()?;
+                } else {
+                    builder.append_value(array.value(i) as $TO_TY)?;
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }) as CompiledCastFunction
+    }};
+}
+
+/// Builds a `CompiledCastFunction` that casts from `from_type` to `to_type`, or `None` if the
+/// combination is not supported.
+///
+/// Only numeric widening/narrowing is supported for now. The request this was built for
+/// ("chunk0-2") also asks for string casts (`CAST(col AS Utf8)`, `CAST(string_col AS Int32)`),
+/// but this crate's Utf8 array (built via `BinaryBuilder`/`append_string` in `literal.rs`) has
+/// no verified value-reading API elsewhere in this codebase to parse/format against, and
+/// guessing one risks repeating the unverified-API regression this file already shipped once
+/// (see the `arrow::compute`/`DataType::Null` fix). Tracked as a known gap rather than guessed
+/// at: add string<->numeric arms here once the real accessor is confirmed against this crate's
+/// pinned `arrow` version.
+fn compile_cast_function(from_type: &DataType, to_type: &DataType) -> Option<CompiledCastFunction> {
+    macro_rules! cast_to {
+        ($FROM_ARR:ident) => {
+            match to_type {
+                DataType::Int8 => Some(cast_numeric_array!($FROM_ARR, Int8Builder, i8)),
+                DataType::Int16 => Some(cast_numeric_array!($FROM_ARR, Int16Builder, i16)),
+                DataType::Int32 => Some(cast_numeric_array!($FROM_ARR, Int32Builder, i32)),
+                DataType::Int64 => Some(cast_numeric_array!($FROM_ARR, Int64Builder, i64)),
+                DataType::UInt8 => Some(cast_numeric_array!($FROM_ARR, UInt8Builder, u8)),
+                DataType::UInt16 => Some(cast_numeric_array!($FROM_ARR, UInt16Builder, u16)),
+                DataType::UInt32 => Some(cast_numeric_array!($FROM_ARR, UInt32Builder, u32)),
+                DataType::UInt64 => Some(cast_numeric_array!($FROM_ARR, UInt64Builder, u64)),
+                DataType::Float32 => Some(cast_numeric_array!($FROM_ARR, Float32Builder, f32)),
+                DataType::Float64 => Some(cast_numeric_array!($FROM_ARR, Float64Builder, f64)),
+                _ => None,
+            }
+        };
+    }
+    match from_type {
+        DataType::Int8 => cast_to!(Int8Array),
+        DataType::Int16 => cast_to!(Int16Array),
+        DataType::Int32 => cast_to!(Int32Array),
+        DataType::Int64 => cast_to!(Int64Array),
+        DataType::UInt8 => cast_to!(UInt8Array),
+        DataType::UInt16 => cast_to!(UInt16Array),
+        DataType::UInt32 => cast_to!(UInt32Array),
+        DataType::UInt64 => cast_to!(UInt64Array),
+        DataType::Float32 => cast_to!(Float32Array),
+        DataType::Float64 => cast_to!(Float64Array),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int32_array(values: Vec<Option<i32>>) -> ArrayRef {
+        let mut builder = Int32Builder::new(values.len());
+        for v in values {
+            match v {
+                Some(v) => builder.append_value(v).unwrap(),
+                None => builder.append_null().unwrap(),
+            }
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    }
+
+    #[test]
+    fn widening_cast_preserves_values_and_nulls() {
+        let array = int32_array(vec![Some(1), None, Some(3)]);
+        let cast_fn = compile_cast_function(&DataType::Int32, &DataType::Int64).unwrap();
+        let result = cast_fn(&array).unwrap();
+        let result = result.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(result.is_null(1), true);
+        assert_eq!(result.value(0), 1);
+        assert_eq!(result.value(2), 3);
+    }
+
+    #[test]
+    fn narrowing_cast_truncates_to_the_target_width() {
+        let array = int32_array(vec![Some(300)]);
+        let cast_fn = compile_cast_function(&DataType::Int32, &DataType::Int8).unwrap();
+        let result = cast_fn(&array).unwrap();
+        let result = result.as_any().downcast_ref::<Int8Array>().unwrap();
+        assert_eq!(result.value(0), 300 as i8);
+    }
+
+    #[test]
+    fn int_to_float_cast_is_supported() {
+        let array = int32_array(vec![Some(2)]);
+        let cast_fn = compile_cast_function(&DataType::Int32, &DataType::Float64).unwrap();
+        let result = cast_fn(&array).unwrap();
+        let result = result.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(result.value(0), 2.0);
+    }
+
+    #[test]
+    fn utf8_casts_are_not_supported() {
+        assert!(compile_cast_function(&DataType::Utf8, &DataType::Int32).is_none());
+        assert!(compile_cast_function(&DataType::Int32, &DataType::Utf8).is_none());
+    }
+}
+
+/// Casts the output of another expression to `cast_type`.
+#[derive(Debug)]
+pub struct CastExpr {
+    expr: Arc<dyn PhysicalExpr>,
+    cast_type: DataType,
+}
+
+impl CastExpr {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, cast_type: DataType) -> Self {
+        CastExpr { expr, cast_type }
+    }
+}
+
+impl PhysicalExpr for CastExpr {
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(self.cast_type.clone())
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        self.expr.nullable(input_schema)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let array = self.expr.evaluate(batch)?;
+        match compile_cast_function(&array.data_type(), &self.cast_type) {
+            Some(cast_fn) => cast_fn(&array),
+            None => Err(ExecutionError::General(format!(
+                "Unsupported CAST from {:?} to {:?}",
+                array.data_type(),
+                self.cast_type
+            ))),
+        }
+    }
+}
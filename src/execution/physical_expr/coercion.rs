@@ -0,0 +1,135 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Numeric type coercion for binary expressions, so that e.g. `int32_col + int64_col`
+//! doesn't have to be written as `CAST(int32_col AS Int64) + int64_col`.
+
+use arrow::datatypes::DataType;
+
+use super::super::super::logicalplan::Operator;
+
+/// Computes the common type that both sides of `op` should be cast to before the
+/// operation is applied, or `None` if `lhs`/`rhs` cannot be reconciled.
+///
+/// Numeric types widen to the wider of the two (e.g. `Int32 op Int64` -> `Int64`), and
+/// mixing an integer with a floating point type always widens to `Float64`. Logical
+/// operators (`AND`/`OR`) require both sides to already be `Boolean` and are not coerced.
+pub fn coerce_types(lhs: &DataType, rhs: &DataType, op: &Operator) -> Option<DataType> {
+    match op {
+        Operator::And | Operator::Or => match (lhs, rhs) {
+            (DataType::Boolean, DataType::Boolean) => Some(DataType::Boolean),
+            _ => None,
+        },
+        _ if lhs == rhs => Some(lhs.clone()),
+        _ => numeric_coercion(lhs, rhs),
+    }
+}
+
+/// Rank used to pick the "wider" of two numeric types. Higher is wider.
+fn numeric_rank(dt: &DataType) -> Option<u8> {
+    match dt {
+        DataType::Int8 | DataType::UInt8 => Some(1),
+        DataType::Int16 | DataType::UInt16 => Some(2),
+        DataType::Int32 | DataType::UInt32 => Some(3),
+        DataType::Int64 | DataType::UInt64 => Some(4),
+        DataType::Float32 => Some(5),
+        DataType::Float64 => Some(6),
+        _ => None,
+    }
+}
+
+fn is_float(dt: &DataType) -> bool {
+    match dt {
+        DataType::Float32 | DataType::Float64 => true,
+        _ => false,
+    }
+}
+
+fn numeric_coercion(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    let lhs_rank = numeric_rank(lhs)?;
+    let rhs_rank = numeric_rank(rhs)?;
+    if is_float(lhs) || is_float(rhs) {
+        Some(DataType::Float64)
+    } else if lhs_rank >= rhs_rank {
+        // same-width signed/unsigned mismatches (e.g. Int32 vs UInt32) fall back to
+        // keeping the left-hand side's type rather than picking a wider type neither
+        // side asked for
+        Some(lhs.clone())
+    } else {
+        Some(rhs.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_type_is_not_coerced() {
+        assert_eq!(
+            coerce_types(&DataType::Int32, &DataType::Int32, &Operator::Plus),
+            Some(DataType::Int32)
+        );
+    }
+
+    #[test]
+    fn wider_int_wins() {
+        assert_eq!(
+            coerce_types(&DataType::Int32, &DataType::Int64, &Operator::Plus),
+            Some(DataType::Int64)
+        );
+        assert_eq!(
+            coerce_types(&DataType::Int64, &DataType::Int32, &Operator::Plus),
+            Some(DataType::Int64)
+        );
+    }
+
+    #[test]
+    fn int_and_float_widen_to_float64() {
+        assert_eq!(
+            coerce_types(&DataType::Int32, &DataType::Float32, &Operator::Plus),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            coerce_types(&DataType::Float64, &DataType::Int64, &Operator::Multiply),
+            Some(DataType::Float64)
+        );
+    }
+
+    #[test]
+    fn mismatched_sign_keeps_left_hand_side() {
+        assert_eq!(
+            coerce_types(&DataType::Int32, &DataType::UInt32, &Operator::Plus),
+            Some(DataType::Int32)
+        );
+    }
+
+    #[test]
+    fn and_or_require_matching_boolean_sides() {
+        assert_eq!(
+            coerce_types(&DataType::Boolean, &DataType::Boolean, &Operator::And),
+            Some(DataType::Boolean)
+        );
+        assert_eq!(coerce_types(&DataType::Int32, &DataType::Int32, &Operator::And), None);
+        assert_eq!(coerce_types(&DataType::Boolean, &DataType::Int32, &Operator::Or), None);
+    }
+
+    #[test]
+    fn incompatible_types_are_not_coerced() {
+        assert_eq!(
+            coerce_types(&DataType::Utf8, &DataType::Int32, &Operator::Plus),
+            None
+        );
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::error::Result;
+use super::PhysicalExpr;
+
+/// Reference to a column by index in the input schema.
+///
+/// Not unit tested directly: every method is a one-line passthrough to `Schema`/`RecordBatch`,
+/// and this crate snapshot has no verified constructor for either (see the `build_array`/
+/// `compile_cast_function` tests in `literal.rs`/`cast.rs`, which test the same surrounding
+/// logic without needing one).
+#[derive(Debug)]
+pub struct Column {
+    index: usize,
+}
+
+impl Column {
+    pub fn new(index: usize) -> Self {
+        Column { index }
+    }
+}
+
+impl PhysicalExpr for Column {
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        Ok(input_schema.field(self.index).data_type().clone())
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        Ok(input_schema.field(self.index).is_nullable())
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        Ok(batch.column(self.index).clone())
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::super::logicalplan::ScalarValue;
+use super::super::error::{ExecutionError, Result};
+use super::PhysicalExpr;
+
+macro_rules! build_constant_array {
+    ($BUILDER:ident, $VALUE:expr, $LEN:expr) => {{
+        let mut builder = $BUILDER::new($LEN);
+        for _ in 0..$LEN {
+            builder.append_value($VALUE)?;
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    }};
+}
+
+/// A constant value, materialized into an array the length of the batch when evaluated.
+#[derive(Debug)]
+pub struct Literal {
+    value: ScalarValue,
+}
+
+impl Literal {
+    pub fn new(value: ScalarValue) -> Self {
+        Literal { value }
+    }
+}
+
+impl PhysicalExpr for Literal {
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        match self.value {
+            // A bare `NULL` literal carries no type information of its own, and
+            // `build_array` cannot materialize it either, even under a `Cast`.
+            ScalarValue::Null => Err(ExecutionError::NotImplemented),
+            ScalarValue::Boolean(_) => Ok(DataType::Boolean),
+            ScalarValue::Int8(_) => Ok(DataType::Int8),
+            ScalarValue::Int16(_) => Ok(DataType::Int16),
+            ScalarValue::Int32(_) => Ok(DataType::Int32),
+            ScalarValue::Int64(_) => Ok(DataType::Int64),
+            ScalarValue::UInt8(_) => Ok(DataType::UInt8),
+            ScalarValue::UInt16(_) => Ok(DataType::UInt16),
+            ScalarValue::UInt32(_) => Ok(DataType::UInt32),
+            ScalarValue::UInt64(_) => Ok(DataType::UInt64),
+            ScalarValue::Float32(_) => Ok(DataType::Float32),
+            ScalarValue::Float64(_) => Ok(DataType::Float64),
+            ScalarValue::Utf8(_) => Ok(DataType::Utf8),
+        }
+    }
+
+    fn nullable(&self, _input_schema: &Schema) -> Result<bool> {
+        Ok(self.value == ScalarValue::Null)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        build_array(&self.value, batch.num_rows())
+    }
+}
+
+/// Materializes `value`, repeated `len` times, into an array. Split out of `evaluate()` so
+/// the per-type builder logic can be unit tested without needing a `RecordBatch`.
+fn build_array(value: &ScalarValue, len: usize) -> Result<ArrayRef> {
+    Ok(match *value {
+        ScalarValue::Null => Err(ExecutionError::NotImplemented)?,
+        ScalarValue::Boolean(v) => build_constant_array!(BooleanBuilder, v, len),
+        ScalarValue::Int8(v) => build_constant_array!(Int8Builder, v, len),
+        ScalarValue::Int16(v) => build_constant_array!(Int16Builder, v, len),
+        ScalarValue::Int32(v) => build_constant_array!(Int32Builder, v, len),
+        ScalarValue::Int64(v) => build_constant_array!(Int64Builder, v, len),
+        ScalarValue::UInt8(v) => build_constant_array!(UInt8Builder, v, len),
+        ScalarValue::UInt16(v) => build_constant_array!(UInt16Builder, v, len),
+        ScalarValue::UInt32(v) => build_constant_array!(UInt32Builder, v, len),
+        ScalarValue::UInt64(v) => build_constant_array!(UInt64Builder, v, len),
+        ScalarValue::Float32(v) => build_constant_array!(Float32Builder, v, len),
+        ScalarValue::Float64(v) => build_constant_array!(Float64Builder, v, len),
+        ScalarValue::Utf8(ref v) => {
+            let mut builder = BinaryBuilder::new(len);
+            for _ in 0..len {
+                builder.append_string(v)?;
+            }
+            Arc::new(builder.finish()) as ArrayRef
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `PhysicalExpr::evaluate`/`data_type`/`nullable` all take a `RecordBatch`/`Schema`
+    // reference, and this crate snapshot has no verified constructor for either anywhere in
+    // the tree (`Schema::new`/`RecordBatch::new` are never called). Rather than guess at
+    // their signatures, these tests exercise `build_array` directly, which carries all of
+    // `evaluate`'s per-type builder logic.
+
+    #[test]
+    fn build_array_repeats_the_value_for_every_row() {
+        let array = build_array(&ScalarValue::Int32(7), 3).unwrap();
+        let array = array.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(array.len(), 3);
+        assert_eq!((0..3).map(|i| array.value(i)).collect::<Vec<_>>(), vec![7, 7, 7]);
+    }
+
+    #[test]
+    fn build_array_supports_every_numeric_width() {
+        assert_eq!(
+            build_array(&ScalarValue::Int8(1), 1).unwrap().as_any().downcast_ref::<Int8Array>().unwrap().value(0),
+            1
+        );
+        assert_eq!(
+            build_array(&ScalarValue::UInt64(9), 1)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap()
+                .value(0),
+            9
+        );
+        assert_eq!(
+            build_array(&ScalarValue::Float64(1.5), 1)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap()
+                .value(0),
+            1.5
+        );
+    }
+
+    #[test]
+    fn build_array_repeats_a_boolean_value() {
+        let array = build_array(&ScalarValue::Boolean(true), 2).unwrap();
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(array.value(0), true);
+        assert_eq!(array.value(1), true);
+    }
+
+    #[test]
+    fn build_array_repeats_a_string_value() {
+        // No verified API exists anywhere in this crate snapshot for reading a value back out
+        // of a `BinaryArray` (see the gap documented on `compile_cast_function` in cast.rs), so
+        // this only checks that building one `len` times long succeeds and has the right length.
+        let array = build_array(&ScalarValue::Utf8("hi".to_string()), 2).unwrap();
+        let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(array.len(), 2);
+    }
+
+    #[test]
+    fn build_array_rejects_a_bare_null_literal() {
+        assert!(build_array(&ScalarValue::Null, 1).is_err());
+    }
+}
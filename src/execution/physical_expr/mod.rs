@@ -0,0 +1,57 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Physical expressions are the compiled, type-aware counterpart of a logical `Expr`.
+//!
+//! Unlike the old closure-based `CompiledExpr`, a `PhysicalExpr` can be asked for its
+//! output data type and nullability against an input schema without being evaluated,
+//! which lets the planner do type checking ahead of execution.
+
+use std::fmt::Debug;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::error::Result;
+
+pub mod aggregate;
+pub mod binary_expr;
+pub mod cast;
+pub mod coercion;
+pub mod column;
+pub mod literal;
+pub mod scalar_function;
+pub mod unary_expr;
+
+pub use self::aggregate::AggregateExpr;
+pub use self::binary_expr::BinaryExpr;
+pub use self::cast::CastExpr;
+pub use self::coercion::coerce_types;
+pub use self::column::Column;
+pub use self::literal::Literal;
+pub use self::scalar_function::ScalarFunctionExpr;
+pub use self::unary_expr::{IsNullExpr, NotExpr};
+
+/// A compiled expression that can be evaluated against a `RecordBatch`.
+pub trait PhysicalExpr: Debug {
+    /// The data type this expression produces when evaluated against `input_schema`.
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType>;
+
+    /// Whether this expression can produce nulls when evaluated against `input_schema`.
+    fn nullable(&self, input_schema: &Schema) -> Result<bool>;
+
+    /// Evaluate the expression against a batch, producing an array of the same length.
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef>;
+}
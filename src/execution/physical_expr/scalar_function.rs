@@ -0,0 +1,86 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::error::Result;
+use super::PhysicalExpr;
+
+/// A scalar function registered with an `ExecutionContext`, e.g. `sqrt` or `length`.
+pub struct ScalarFunction {
+    pub name: String,
+    pub arg_types: Vec<DataType>,
+    pub return_type: DataType,
+    pub fun: Rc<Fn(&[ArrayRef]) -> Result<ArrayRef>>,
+}
+
+impl ScalarFunction {
+    pub fn new(
+        name: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+        fun: Rc<Fn(&[ArrayRef]) -> Result<ArrayRef>>,
+    ) -> Self {
+        ScalarFunction {
+            name: name.to_string(),
+            arg_types,
+            return_type,
+            fun,
+        }
+    }
+}
+
+/// A call to a registered scalar function, e.g. `sqrt(a)`.
+pub struct ScalarFunctionExpr {
+    fun: Rc<ScalarFunction>,
+    args: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+impl ScalarFunctionExpr {
+    pub fn new(fun: Rc<ScalarFunction>, args: Vec<Arc<dyn PhysicalExpr>>) -> Self {
+        ScalarFunctionExpr { fun, args }
+    }
+}
+
+impl ::std::fmt::Debug for ScalarFunctionExpr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "ScalarFunctionExpr({})", self.fun.name)
+    }
+}
+
+impl PhysicalExpr for ScalarFunctionExpr {
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(self.fun.return_type.clone())
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        for arg in &self.args {
+            if arg.nullable(input_schema)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let arg_values: Result<Vec<ArrayRef>> =
+            self.args.iter().map(|arg| arg.evaluate(batch)).collect();
+        (self.fun.fun)(&arg_values?)
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow::array::*;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::super::error::Result;
+use super::PhysicalExpr;
+
+/// Logical negation of a boolean expression, nulls stay null.
+#[derive(Debug)]
+pub struct NotExpr {
+    arg: Arc<dyn PhysicalExpr>,
+}
+
+impl NotExpr {
+    pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
+        NotExpr { arg }
+    }
+}
+
+impl PhysicalExpr for NotExpr {
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        self.arg.nullable(input_schema)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let values = self.arg.evaluate(batch)?;
+        let values = values.as_any().downcast_ref::<BooleanArray>().unwrap();
+        let mut builder = BooleanBuilder::new(values.len());
+        for i in 0..values.len() {
+            if values.is_null(i) {
+                builder.append_null()?;
+            } else {
+                builder.append_value(!values.value(i))?;
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+/// `expr IS NULL` / `expr IS NOT NULL`, derived from the underlying array's null bitmap.
+#[derive(Debug)]
+pub struct IsNullExpr {
+    arg: Arc<dyn PhysicalExpr>,
+    negated: bool,
+}
+
+impl IsNullExpr {
+    pub fn is_null(arg: Arc<dyn PhysicalExpr>) -> Self {
+        IsNullExpr {
+            arg,
+            negated: false,
+        }
+    }
+
+    pub fn is_not_null(arg: Arc<dyn PhysicalExpr>) -> Self {
+        IsNullExpr { arg, negated: true }
+    }
+}
+
+impl PhysicalExpr for IsNullExpr {
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, _input_schema: &Schema) -> Result<bool> {
+        // `IS [NOT] NULL` is always defined, even for a null input
+        Ok(false)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let values = self.arg.evaluate(batch)?;
+        let mut builder = BooleanBuilder::new(values.len());
+        for i in 0..values.len() {
+            builder.append_value(values.is_null(i) != self.negated)?;
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
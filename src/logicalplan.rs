@@ -0,0 +1,114 @@
+// Copyright 2018 Grove Enterprises LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Logical query plan and the `Expr` tree used to describe it.
+
+use std::hash::{Hash, Hasher};
+
+use arrow::datatypes::DataType;
+
+/// A literal value in a logical expression.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum ScalarValue {
+    Null,
+    Boolean(bool),
+    Int8(i8),
+    Int16(i16),
+    Int32(i32),
+    Int64(i64),
+    UInt8(u8),
+    UInt16(u16),
+    UInt32(u32),
+    UInt64(u64),
+    Float32(f32),
+    Float64(f64),
+    Utf8(String),
+}
+
+impl Eq for ScalarValue {}
+
+/// Hashed by bit pattern for floats, so that `COUNT(DISTINCT ...)` and similar can use a
+/// `ScalarValue` as a hash set/map key. As with `PartialOrd`, this only makes sense when
+/// comparing values of the same variant (a single, consistently-typed column).
+impl Hash for ScalarValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            ScalarValue::Null => 0u8.hash(state),
+            ScalarValue::Boolean(v) => v.hash(state),
+            ScalarValue::Int8(v) => v.hash(state),
+            ScalarValue::Int16(v) => v.hash(state),
+            ScalarValue::Int32(v) => v.hash(state),
+            ScalarValue::Int64(v) => v.hash(state),
+            ScalarValue::UInt8(v) => v.hash(state),
+            ScalarValue::UInt16(v) => v.hash(state),
+            ScalarValue::UInt32(v) => v.hash(state),
+            ScalarValue::UInt64(v) => v.hash(state),
+            ScalarValue::Float32(v) => v.to_bits().hash(state),
+            ScalarValue::Float64(v) => v.to_bits().hash(state),
+            ScalarValue::Utf8(v) => v.hash(state),
+        }
+    }
+}
+
+/// Binary operators supported by `Expr::BinaryExpr`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    And,
+    Or,
+}
+
+/// Relational expression tree.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    /// Reference to a column by index in the input schema
+    Column(usize),
+    /// Constant value
+    Literal(ScalarValue),
+    /// Binary expression e.g. `a + b`, `a > b`
+    BinaryExpr {
+        left: Box<Expr>,
+        op: Operator,
+        right: Box<Expr>,
+    },
+    /// Logical negation of a boolean expression
+    Not(Box<Expr>),
+    /// `expr IS NULL`
+    IsNull(Box<Expr>),
+    /// `expr IS NOT NULL`
+    IsNotNull(Box<Expr>),
+    /// Cast an expression to a given data type
+    Cast { expr: Box<Expr>, data_type: DataType },
+    /// Call to a scalar function registered with the `ExecutionContext`, e.g. `sqrt(a)`
+    ScalarFunction {
+        name: String,
+        args: Vec<Expr>,
+        return_type: DataType,
+    },
+    /// Call to an aggregate function e.g. `SUM(a)`
+    AggregateFunction {
+        name: String,
+        args: Vec<Expr>,
+        return_type: DataType,
+    },
+}